@@ -1,8 +1,13 @@
 // ```
 // [dependencies]
-// chrono = "0.4.38"
+// chrono = { version = "0.4.38", features = ["serde"] }
 // pcap = "2.2.0"
 // pnet = "0.35.0"
+// rayon = "1.10.0"
+// memmap2 = "0.9.5"
+// zerocopy = { version = "0.8.14", features = ["derive"] }
+// serde = { version = "1.0", features = ["derive"] }
+// serde_json = "1.0"
 // ```
 
 // NOTE: run with:
@@ -12,9 +17,28 @@
 //
 // cargo run --release -- --heap <path/to.cpap> ran
 //   1.07 ± 0.01 times faster than cargo run --release -- --vec <path/to.cpap>
+//
+// add `--bin` to either mode to emit QuotePacket::RECORD_SIZE-byte fixed records
+// instead of Display text, e.g.:
+// cargo run --release -- --heap --bin <path/to.cpap> > quotes.bin
+//
+// capture live instead of from a file with `--device <iface>` in place of the path:
+// cargo run --release -- --heap --device eth0
+//
+// use `--parallel` in place of `--vec`/`--heap` to parse an offline capture across rayon's
+// thread pool:
+// cargo run --release -- --parallel <path/to.cpap>
+//
+// add `--mmap` to read an offline capture through the native zero-copy reader instead of
+// libpcap (see the NOTE on `Source::from_mmap` / `mmap_capture`). Classic pcap only —
+// pcapng is out of scope for this reader and is rejected:
+// cargo run --release -- --heap --mmap <path/to.cpap>
+//
+// add `--json` to emit one NDJSON event per flushed quote instead of Display text:
+// cargo run --release -- --heap --json <path/to.cpap> | jq .
 
 use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
-use pcap::{Capture, Offline};
+use pcap::{Active, Capture, Offline};
 use pnet::packet::{
     ethernet::{EtherTypes, EthernetPacket},
     ip::IpNextHeaderProtocols,
@@ -22,11 +46,87 @@ use pnet::packet::{
     udp::UdpPacket,
     Packet,
 };
-use std::{collections::BinaryHeap, fmt::Debug};
+use mmap_capture::MmapCapture;
+use rayon::prelude::*;
+use std::{collections::BinaryHeap, fmt::Debug, io::Write};
+
+mod mmap_capture;
 
 /// The maximum delay between accept and packet times
 const MAX_DELAY: TimeDelta = TimeDelta::seconds(3);
 
+/// BPF filter for the multicast UDP carrying the B6034 feed.
+const B6034_FILTER: &str = "udp and dst net 224.0.0.0/4";
+
+/// How long `Source::Live` waits for a packet before returning `SourceEvent::Timeout`.
+const LIVE_TIMEOUT_MS: i32 = 100;
+
+/// Batch size for `with_parallel`'s rayon-parsed chunks.
+const PARALLEL_BATCH_SIZE: usize = 4096;
+
+/// A packet source, abstracting over an offline capture file and a live network device.
+enum Source {
+    Offline(Capture<Offline>),
+    Live(Capture<Active>),
+    Mmap(MmapCapture),
+}
+
+/// The result of pulling the next record out of a `Source`, down to the raw ethernet frame.
+enum SourceEvent<'a> {
+    Frame(Result<DateTime<Utc>, &'static str>, &'a [u8]),
+    /// No record within `Source::Live`'s poll timeout; not end of stream.
+    Timeout,
+    Eof,
+}
+
+impl Source {
+    /// Opens `path` as an offline capture file.
+    fn from_file(path: &str) -> Self {
+        Self::Offline(Capture::from_file(path).unwrap())
+    }
+
+    /// Opens `device` for live capture, filtered down to the B6034 multicast feed.
+    fn from_device(device: &str) -> Self {
+        let mut capture = Capture::from_device(device)
+            .unwrap()
+            .promisc(true)
+            .timeout(LIVE_TIMEOUT_MS)
+            .open()
+            .unwrap();
+
+        capture.filter(B6034_FILTER, true).unwrap();
+
+        Self::Live(capture)
+    }
+
+    /// Memory-maps `path` and walks it directly, bypassing `libpcap` for the offline path.
+    fn from_mmap(path: &str) -> Self {
+        Self::Mmap(MmapCapture::open(path).unwrap())
+    }
+
+    fn is_live(&self) -> bool {
+        matches!(self, Self::Live(_))
+    }
+
+    fn next_event(&mut self) -> SourceEvent<'_> {
+        match self {
+            Self::Offline(capture) => match capture.next_packet() {
+                Ok(packet) => SourceEvent::Frame(QuotePacket::packet_time_of(packet.header), packet.data),
+                Err(_) => SourceEvent::Eof,
+            },
+            Self::Live(capture) => match capture.next_packet() {
+                Ok(packet) => SourceEvent::Frame(QuotePacket::packet_time_of(packet.header), packet.data),
+                Err(pcap::Error::TimeoutExpired) => SourceEvent::Timeout,
+                Err(_) => SourceEvent::Eof,
+            },
+            Self::Mmap(mmap_capture) => match mmap_capture.next_record() {
+                Some((packet_time, frame)) => SourceEvent::Frame(Ok(packet_time), frame),
+                None => SourceEvent::Eof,
+            },
+        }
+    }
+}
+
 /// A convenient char array for issue codes.
 #[derive(Copy, Clone, Debug)]
 pub struct IssueCode([char; 12]);
@@ -35,7 +135,7 @@ impl TryFrom<&str> for IssueCode {
     type Error = &'static str;
 
     fn try_from(str: &str) -> Result<Self, Self::Error> {
-        if str.len() != 12 {
+        if str.chars().count() != 12 {
             return Err("Must be 12 characters");
         }
 
@@ -45,6 +145,20 @@ impl TryFrom<&str> for IssueCode {
     }
 }
 
+impl IssueCode {
+    /// Returns the issue code as raw ASCII bytes, for the `--bin` record layout.
+    fn to_ascii_bytes(self) -> [u8; 12] {
+        self.0.map(|char| char as u8)
+    }
+
+    /// Parses an issue code back from the raw ASCII bytes written by `to_ascii_bytes`.
+    fn from_ascii_bytes(bytes: [u8; 12]) -> Result<Self, &'static str> {
+        std::str::from_utf8(&bytes)
+            .map_err(|_| QuotePacket::UTF8_ERROR)?
+            .try_into()
+    }
+}
+
 /// A wrapper of `QuotePacket` to use `BinaryHeap` as a min-heap.
 #[derive(Copy, Clone, Debug)]
 pub struct OrdQuotePacket(QuotePacket);
@@ -69,6 +183,79 @@ impl Ord for OrdQuotePacket {
     }
 }
 
+/// A cursor into one of `with_parallel`'s sorted batches, ordered by `accept_time`
+/// (reversed for `BinaryHeap`'s max-heap).
+struct BatchCursor {
+    accept_time: DateTime<Utc>,
+    batch_idx: usize,
+    pos: usize,
+}
+
+impl PartialEq for BatchCursor {
+    fn eq(&self, other: &Self) -> bool {
+        self.accept_time == other.accept_time
+    }
+}
+
+impl Eq for BatchCursor {}
+
+impl PartialOrd for BatchCursor {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BatchCursor {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.accept_time.cmp(&other.accept_time).reverse()
+    }
+}
+
+/// A cursor over a byte slice that advances as fields are read off it.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    /// Advances the cursor by `n` bytes without reading them.
+    fn skip(&mut self, n: usize) {
+        self.pos += n;
+    }
+
+    /// Reads and returns the next `n` bytes, advancing the cursor.
+    fn take(&mut self, n: usize) -> Result<&'a [u8], &'static str> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + n)
+            .ok_or(QuotePacket::TRUNCATED_ERROR)?;
+        self.pos += n;
+
+        Ok(bytes)
+    }
+
+    /// Reads `len` ASCII digits and parses them as a `u32`.
+    fn ascii_u32(&mut self, len: usize) -> Result<u32, &'static str> {
+        let bytes = self.take(len)?;
+        let str = std::str::from_utf8(bytes).map_err(|_| QuotePacket::UTF8_ERROR)?;
+
+        str.parse().map_err(|_| QuotePacket::PARSE_INT_ERROR)
+    }
+
+    /// Reads and checks that the next bytes match `tag`.
+    fn expect(&mut self, tag: &[u8]) -> Result<(), &'static str> {
+        if self.take(tag.len())? == tag {
+            Ok(())
+        } else {
+            Err(QuotePacket::TAG_ERROR)
+        }
+    }
+}
+
 /// Best bids/asks prices/quantities of a quote.
 #[derive(Copy, Clone, Debug)]
 pub struct QuotePacket {
@@ -108,14 +295,30 @@ impl QuotePacket {
     pub const DATE_ERROR: &'static str = "Invalid date";
     pub const PARSE_INT_ERROR: &'static str = "Cannot parse number";
     pub const UTF8_ERROR: &'static str = "Invalid UTF-8";
+    pub const RECORD_SIZE_ERROR: &'static str = "Truncated record";
+    pub const TRUNCATED_ERROR: &'static str = "Truncated packet";
+    pub const TAG_ERROR: &'static str = "Unexpected tag";
+
+    /// Size in bytes of the fixed-layout binary record produced by `write_to`/`from_bytes`.
+    pub const RECORD_SIZE: usize = 108;
 
     /// Returns the quote from a `packet`, if any.
     pub fn try_from_packet(packet: pcap::Packet) -> Option<Result<Self, &'static str>> {
-        let packet_time = DateTime::from_timestamp_micros(
-            packet.header.ts.tv_sec * 1_000_000 + i64::from(packet.header.ts.tv_usec),
-        );
+        Self::try_from_frame(Self::packet_time_of(packet.header), packet.data)
+    }
 
-        EthernetPacket::new(packet.data)
+    /// Returns the `libpcap` packet header's timestamp, converted to a `DateTime<Utc>`.
+    fn packet_time_of(header: &pcap::PacketHeader) -> Result<DateTime<Utc>, &'static str> {
+        DateTime::from_timestamp_micros(header.ts.tv_sec * 1_000_000 + i64::from(header.ts.tv_usec))
+            .ok_or(Self::DATE_ERROR)
+    }
+
+    /// Returns the quote from an ethernet `frame` captured at `packet_time`, if any.
+    pub fn try_from_frame(
+        packet_time: Result<DateTime<Utc>, &'static str>,
+        frame: &[u8],
+    ) -> Option<Result<Self, &'static str>> {
+        EthernetPacket::new(frame)
             .as_ref()
             .and_then(|ethernet_packet| match ethernet_packet.get_ethertype() {
                 EtherTypes::Ipv4 => Ipv4Packet::new(ethernet_packet.payload()),
@@ -128,10 +331,8 @@ impl QuotePacket {
             .and_then(|ipv4_packet| UdpPacket::new(ipv4_packet.payload()))
             .as_ref()
             .map(|udp_packet| udp_packet.payload())
-            .filter(|udp_payload| &udp_payload[..5] == Self::DATA_INFO_MARKET)
-            .map(|udp_payload| {
-                Self::try_from_udp_payload(packet_time.ok_or(Self::DATE_ERROR)?, udp_payload)
-            })
+            .filter(|udp_payload| udp_payload.get(..5) == Some(Self::DATA_INFO_MARKET.as_slice()))
+            .map(|udp_payload| Self::try_from_udp_payload(packet_time?, udp_payload))
     }
 
     /// Returns the quote from a `udp_payload`.
@@ -139,41 +340,49 @@ impl QuotePacket {
         packet_time: DateTime<Utc>,
         udp_payload: &[u8],
     ) -> Result<Self, &'static str> {
-        debug_assert!(&udp_payload[..5] == Self::DATA_INFO_MARKET);
         debug_assert!(udp_payload.last() == Some(&0xFF));
 
-        // NOTE: there are ways to make this code a little faster if we allow unsafe
-        let from_utf8 = |bytes| std::str::from_utf8(bytes).map_err(|_| Self::UTF8_ERROR);
-        let parse_u32 = |str| u32::from_str_radix(str, 10).map_err(|_| Self::PARSE_INT_ERROR);
-        let parse = |start, len| parse_u32(from_utf8(&udp_payload[start..start + len])?);
-        let parse_price = |start| parse(start, 5);
-        let parse_quantity = |start| parse(start, 7);
-
-        let issue_code = from_utf8(&udp_payload[5..17])?.try_into()?;
-        let bid_price_1 = parse_price(29)?;
-        let bid_quantity_1 = parse_quantity(34)?;
-        let bid_price_2 = parse_price(41)?;
-        let bid_quantity_2 = parse_quantity(46)?;
-        let bid_price_3 = parse_price(53)?;
-        let bid_quantity_3 = parse_quantity(58)?;
-        let bid_price_4 = parse_price(65)?;
-        let bid_quantity_4 = parse_quantity(70)?;
-        let bid_price_5 = parse_price(77)?;
-        let bid_quantity_5 = parse_quantity(82)?;
-        let ask_price_1 = parse_price(96)?;
-        let ask_quantity_1 = parse_quantity(101)?;
-        let ask_price_2 = parse_price(108)?;
-        let ask_quantity_2 = parse_quantity(113)?;
-        let ask_price_3 = parse_price(120)?;
-        let ask_quantity_3 = parse_quantity(125)?;
-        let ask_price_4 = parse_price(132)?;
-        let ask_quantity_4 = parse_quantity(137)?;
-        let ask_price_5 = parse_price(144)?;
-        let ask_quantity_5 = parse_quantity(149)?;
+        let mut decoder = Decoder::new(udp_payload);
+
+        decoder.expect(Self::DATA_INFO_MARKET)?;
+        let issue_code = std::str::from_utf8(decoder.take(12)?)
+            .map_err(|_| Self::UTF8_ERROR)?
+            .try_into()?;
+
+        decoder.skip(12); // reserved, before the bid levels
+        let bid_price_1 = decoder.ascii_u32(5)?;
+        let bid_quantity_1 = decoder.ascii_u32(7)?;
+        let bid_price_2 = decoder.ascii_u32(5)?;
+        let bid_quantity_2 = decoder.ascii_u32(7)?;
+        let bid_price_3 = decoder.ascii_u32(5)?;
+        let bid_quantity_3 = decoder.ascii_u32(7)?;
+        let bid_price_4 = decoder.ascii_u32(5)?;
+        let bid_quantity_4 = decoder.ascii_u32(7)?;
+        let bid_price_5 = decoder.ascii_u32(5)?;
+        let bid_quantity_5 = decoder.ascii_u32(7)?;
+
+        decoder.skip(7); // reserved, before the ask levels
+        let ask_price_1 = decoder.ascii_u32(5)?;
+        let ask_quantity_1 = decoder.ascii_u32(7)?;
+        let ask_price_2 = decoder.ascii_u32(5)?;
+        let ask_quantity_2 = decoder.ascii_u32(7)?;
+        let ask_price_3 = decoder.ascii_u32(5)?;
+        let ask_quantity_3 = decoder.ascii_u32(7)?;
+        let ask_price_4 = decoder.ascii_u32(5)?;
+        let ask_quantity_4 = decoder.ascii_u32(7)?;
+        let ask_price_5 = decoder.ascii_u32(5)?;
+        let ask_quantity_5 = decoder.ascii_u32(7)?;
+
+        decoder.skip(50); // reserved, before the accept time
 
         let accept_time = {
             let korea = FixedOffset::east_opt(9 * 60 * 60).ok_or(Self::DATE_ERROR)?;
-            let accept_time = from_utf8(&udp_payload[206..214])?;
+            let accept_time = decoder.take(8)?;
+            let parse_u32 = |bytes| {
+                std::str::from_utf8(bytes)
+                    .map_err(|_| Self::UTF8_ERROR)
+                    .and_then(|str| u32::from_str_radix(str, 10).map_err(|_| Self::PARSE_INT_ERROR))
+            };
             let hours = parse_u32(&accept_time[0..2])?;
             let minutes = parse_u32(&accept_time[2..4])?;
             let seconds = parse_u32(&accept_time[4..6])?;
@@ -218,17 +427,44 @@ impl QuotePacket {
             ask_quantity_5,
         })
     }
-}
 
-impl std::fmt::Display for QuotePacket {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} {} ", self.packet_time, self.accept_time)?;
+    /// Writes this quote as a fixed `RECORD_SIZE`-byte little-endian record (see `from_bytes`).
+    pub fn write_to(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(&(self.accept_time.timestamp_micros() as u64).to_le_bytes())?;
+        writer.write_all(&(self.packet_time.timestamp_micros() as u64).to_le_bytes())?;
+        writer.write_all(&self.issue_code.to_ascii_bytes())?;
 
-        for char in self.issue_code.0 {
-            write!(f, "{char}")?;
+        for (_, price) in self.levels() {
+            writer.write_all(&price.to_le_bytes())?;
+        }
+
+        for (quantity, _) in self.levels() {
+            writer.write_all(&quantity.to_le_bytes())?;
         }
 
-        for (quantity, price) in [
+        Ok(())
+    }
+
+    /// Writes this quote as one NDJSON `QuoteEvent`.
+    pub fn write_json(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        let levels = self.levels();
+        let level = |quantity, price| QuoteLevel { price, quantity };
+
+        let event = QuoteEvent {
+            packet_time: self.packet_time,
+            accept_time: self.accept_time,
+            issue_code: self.issue_code.0.iter().collect(),
+            bids: std::array::from_fn(|i| level(levels[4 - i].0, levels[4 - i].1)),
+            asks: std::array::from_fn(|i| level(levels[5 + i].0, levels[5 + i].1)),
+        };
+
+        serde_json::to_writer(&mut *writer, &event)?;
+        writeln!(writer)
+    }
+
+    /// The ten bid/ask `(quantity, price)` levels, bid_5..bid_1, ask_1..ask_5.
+    fn levels(&self) -> [(u32, u32); 10] {
+        [
             (self.bid_quantity_5, self.bid_price_5),
             (self.bid_quantity_4, self.bid_price_4),
             (self.bid_quantity_3, self.bid_price_3),
@@ -239,7 +475,96 @@ impl std::fmt::Display for QuotePacket {
             (self.ask_quantity_3, self.ask_price_3),
             (self.ask_quantity_4, self.ask_price_4),
             (self.ask_quantity_5, self.ask_price_5),
-        ] {
+        ]
+    }
+
+    /// Parses a quote back from a `RECORD_SIZE`-byte record written by `write_to`.
+    pub fn from_bytes(bytes: &[u8; Self::RECORD_SIZE]) -> Result<Self, &'static str> {
+        let u64_at = |start: usize| u64::from_le_bytes(bytes[start..start + 8].try_into().unwrap());
+        let u32_at = |start: usize| u32::from_le_bytes(bytes[start..start + 4].try_into().unwrap());
+
+        let accept_time =
+            DateTime::from_timestamp_micros(u64_at(0) as i64).ok_or(Self::DATE_ERROR)?;
+        let packet_time =
+            DateTime::from_timestamp_micros(u64_at(8) as i64).ok_or(Self::DATE_ERROR)?;
+        let issue_code = IssueCode::from_ascii_bytes(bytes[16..28].try_into().unwrap())?;
+
+        let bid_price_5 = u32_at(28);
+        let bid_price_4 = u32_at(32);
+        let bid_price_3 = u32_at(36);
+        let bid_price_2 = u32_at(40);
+        let bid_price_1 = u32_at(44);
+        let ask_price_1 = u32_at(48);
+        let ask_price_2 = u32_at(52);
+        let ask_price_3 = u32_at(56);
+        let ask_price_4 = u32_at(60);
+        let ask_price_5 = u32_at(64);
+
+        let bid_quantity_5 = u32_at(68);
+        let bid_quantity_4 = u32_at(72);
+        let bid_quantity_3 = u32_at(76);
+        let bid_quantity_2 = u32_at(80);
+        let bid_quantity_1 = u32_at(84);
+        let ask_quantity_1 = u32_at(88);
+        let ask_quantity_2 = u32_at(92);
+        let ask_quantity_3 = u32_at(96);
+        let ask_quantity_4 = u32_at(100);
+        let ask_quantity_5 = u32_at(104);
+
+        Ok(QuotePacket {
+            packet_time,
+            accept_time,
+            issue_code,
+            bid_price_1,
+            bid_price_2,
+            bid_price_3,
+            bid_price_4,
+            bid_price_5,
+            ask_price_1,
+            ask_price_2,
+            ask_price_3,
+            ask_price_4,
+            ask_price_5,
+            bid_quantity_1,
+            bid_quantity_2,
+            bid_quantity_3,
+            bid_quantity_4,
+            bid_quantity_5,
+            ask_quantity_1,
+            ask_quantity_2,
+            ask_quantity_3,
+            ask_quantity_4,
+            ask_quantity_5,
+        })
+    }
+}
+
+/// One NDJSON bid/ask level, as serialized by `QuotePacket::write_json`.
+#[derive(serde::Serialize)]
+struct QuoteLevel {
+    price: u32,
+    quantity: u32,
+}
+
+/// The NDJSON event serialized by `QuotePacket::write_json`.
+#[derive(serde::Serialize)]
+struct QuoteEvent {
+    packet_time: DateTime<Utc>,
+    accept_time: DateTime<Utc>,
+    issue_code: String,
+    bids: [QuoteLevel; 5],
+    asks: [QuoteLevel; 5],
+}
+
+impl std::fmt::Display for QuotePacket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} ", self.packet_time, self.accept_time)?;
+
+        for char in self.issue_code.0 {
+            write!(f, "{char}")?;
+        }
+
+        for (quantity, price) in self.levels() {
             write!(f, " {quantity:>6}@{price:<6}")?;
         }
 
@@ -247,83 +572,254 @@ impl std::fmt::Display for QuotePacket {
     }
 }
 
+/// The output format for a flushed `QuotePacket`, selected by `--bin`/`--json` (default: `Display` text).
+#[derive(Copy, Clone)]
+enum Format {
+    Text,
+    Bin,
+    Json,
+}
+
 fn main() {
     let mut path = None;
+    let mut device = None;
     let mut is_vec = false;
     let mut is_heap = false;
+    let mut is_bin = false;
+    let mut is_json = false;
+    let mut is_parallel = false;
+    let mut is_mmap = false;
+
+    let mut args = std::env::args().skip(1);
 
-    for arg in std::env::args() {
+    while let Some(arg) = args.next() {
         match arg.as_str() {
             "--vec" => is_vec = true,
             "--heap" => is_heap = true,
+            "--bin" => is_bin = true,
+            "--json" => is_json = true,
+            "--parallel" => is_parallel = true,
+            "--mmap" => is_mmap = true,
+            "--device" => device = Some(args.next().expect("Missing <iface> after `--device`")),
             _ => path = Some(arg),
         }
     }
 
-    // NOTE: it seems like `libpcap` does not buffer the full file into memory!
-    // Otherwise, we'd have to dig into pcap specs and use a cursor to get relevant udp payloads
-    let capture = Capture::from_file(path.expect("Missing `-r <path/to/capture.pcap>`")).unwrap();
+    let format = match (is_bin, is_json) {
+        (false, false) => Format::Text,
+        (true, false) => Format::Bin,
+        (false, true) => Format::Json,
+        (true, true) => panic!("--bin XOR --json"),
+    };
+
+    let source = match (device, is_mmap) {
+        (Some(device), false) => Source::from_device(&device),
+        (Some(_), true) => panic!("--device XOR --mmap"),
+        // NOTE: `libpcap` does not buffer the full file into memory, so `--mmap` bypasses
+        // it entirely to read packets as zero-copy references into the mapped file
+        (None, true) => Source::from_mmap(
+            &path.expect("Missing `-r <path/to/capture.pcap>`"),
+        ),
+        (None, false) => Source::from_file(
+            &path.expect("Missing `-r <path/to/capture.pcap>` or `--device <iface>`"),
+        ),
+    };
+
+    if is_parallel && source.is_live() {
+        panic!("--parallel does not support --device: a live capture never reaches Eof to flush the batched parse");
+    }
 
-    match (is_vec, is_heap) {
-        (true, false) => with_vec(capture),
-        (false, true) => with_heap(capture),
-        _ => panic!("--vec XOR --heap"),
+    match (is_vec, is_heap, is_parallel) {
+        (true, false, false) => with_vec(source, format),
+        (false, true, false) => with_heap(source, format),
+        (false, false, true) => with_parallel(source, format),
+        _ => panic!("--vec XOR --heap XOR --parallel"),
     }
 }
 
-fn with_vec(mut capture: Capture<Offline>) {
-    let mut window = Vec::<QuotePacket>::with_capacity(2048);
+/// Writes `quote_packet` to stdout, in `format`.
+fn emit(quote_packet: &QuotePacket, format: Format) {
+    let mut stdout = std::io::stdout().lock();
 
-    while let Ok(packet) = capture.next_packet() {
-        if let Some(Ok(quote_packet)) = QuotePacket::try_from_packet(packet) {
-            // Flush buffered quotes older than the current one, taking MAX_DELAY into account
-            for quote_packet in window.drain(
-                ..window.partition_point(|probe| {
-                    probe.accept_time + MAX_DELAY < quote_packet.accept_time
-                }),
-            ) {
-                println!("{quote_packet}");
+    match format {
+        Format::Text => println!("{quote_packet}"),
+        Format::Bin => quote_packet.write_to(&mut stdout).expect("Failed to write record"),
+        Format::Json => quote_packet.write_json(&mut stdout).expect("Failed to write event"),
+    }
+}
+
+fn with_vec(mut source: Source, format: Format) {
+    let mut window = Vec::<QuotePacket>::with_capacity(2048);
+    let is_live = source.is_live();
+
+    loop {
+        match source.next_event() {
+            SourceEvent::Frame(packet_time, frame) => {
+                if let Some(Ok(quote_packet)) = QuotePacket::try_from_frame(packet_time, frame) {
+                    // Flush buffered quotes older than the current one, taking MAX_DELAY into account
+                    for quote_packet in window.drain(
+                        ..window.partition_point(|probe| {
+                            probe.accept_time + MAX_DELAY < quote_packet.accept_time
+                        }),
+                    ) {
+                        emit(&quote_packet, format);
+                    }
+
+                    // Insert the current quote in the window at its sorted position
+                    window.insert(
+                        window
+                            .partition_point(|probe| probe.accept_time <= quote_packet.accept_time),
+                        quote_packet,
+                    );
+                }
             }
+            SourceEvent::Timeout => {}
+            SourceEvent::Eof => break,
+        }
+
+        // Live captures can sit quiet for longer than MAX_DELAY with no newer packet to
+        // advance the watermark, so also flush anything stale against the wall clock.
+        if is_live {
+            let now = Utc::now();
 
-            // Insert the current quote in the window at its sorted position
-            window.insert(
-                window.partition_point(|probe| probe.accept_time <= quote_packet.accept_time),
-                quote_packet,
-            );
+            for quote_packet in
+                window.drain(..window.partition_point(|probe| probe.accept_time + MAX_DELAY < now))
+            {
+                emit(&quote_packet, format);
+            }
         }
     }
 
     // Flush the remaining quotes
     for quote_packet in &window {
-        println!("{quote_packet}");
+        emit(quote_packet, format);
     }
 }
 
-fn with_heap(mut capture: Capture<Offline>) {
+fn with_heap(mut source: Source, format: Format) {
     let mut window = BinaryHeap::<OrdQuotePacket>::with_capacity(2048);
-
-    while let Ok(packet) = capture.next_packet() {
-        if let Some(Ok(quote_packet)) = QuotePacket::try_from_packet(packet) {
-            // Flush buffered quotes older than the current one, taking MAX_DELAY into account
-            loop {
-                if let Some(quote_packet) = window
-                    .peek()
-                    .filter(|peek| peek.0.accept_time + MAX_DELAY < quote_packet.accept_time)
-                {
-                    println!("{}", quote_packet.0);
-                    window.pop().unwrap();
-                } else {
-                    break;
+    let is_live = source.is_live();
+
+    loop {
+        match source.next_event() {
+            SourceEvent::Frame(packet_time, frame) => {
+                if let Some(Ok(quote_packet)) = QuotePacket::try_from_frame(packet_time, frame) {
+                    // Flush buffered quotes older than the current one, taking MAX_DELAY into account
+                    loop {
+                        if let Some(quote_packet) = window
+                            .peek()
+                            .filter(|peek| peek.0.accept_time + MAX_DELAY < quote_packet.accept_time)
+                        {
+                            emit(&quote_packet.0, format);
+                            window.pop().unwrap();
+                        } else {
+                            break;
+                        }
+                    }
+
+                    // Insert the current quote in the window
+                    window.push(OrdQuotePacket(quote_packet));
                 }
             }
+            SourceEvent::Timeout => {}
+            SourceEvent::Eof => break,
+        }
 
-            // Insert the current quote in the window
-            window.push(OrdQuotePacket(quote_packet));
+        // Live captures can sit quiet for longer than MAX_DELAY with no newer packet to
+        // advance the watermark, so also flush anything stale against the wall clock.
+        if is_live {
+            let now = Utc::now();
+
+            while let Some(quote_packet) = window
+                .peek()
+                .filter(|peek| peek.0.accept_time + MAX_DELAY < now)
+            {
+                emit(&quote_packet.0, format);
+                window.pop().unwrap();
+            }
         }
     }
 
     // Flush the remaining quotes
     while let Some(quote_packet) = window.pop() {
-        println!("{}", quote_packet.0);
+        emit(&quote_packet.0, format);
+    }
+}
+
+/// Parses `source` across rayon's thread pool, then reorders the result with a k-way merge.
+/// Trades memory for throughput: unlike `with_vec`/`with_heap`'s bounded window, this holds
+/// the whole capture (raw, then parsed) in memory at once before the merge can start.
+fn with_parallel(mut source: Source, format: Format) {
+    let mut raw_packets = Vec::new();
+
+    loop {
+        match source.next_event() {
+            SourceEvent::Frame(packet_time, frame) => raw_packets.push((packet_time, frame.to_vec())),
+            SourceEvent::Timeout => continue,
+            SourceEvent::Eof => break,
+        }
+    }
+
+    // Parse and sort each batch by accept_time concurrently. A quote may only be emitted
+    // once no unparsed/unmerged packet could carry an earlier accept_time within
+    // MAX_DELAY, so the k-way merge below must not outrun the slowest batch's front.
+    let batches: Vec<Vec<QuotePacket>> = raw_packets
+        .par_chunks(PARALLEL_BATCH_SIZE)
+        .map(|chunk| {
+            let mut batch: Vec<QuotePacket> = chunk
+                .iter()
+                .filter_map(|(packet_time, data)| {
+                    QuotePacket::try_from_frame(*packet_time, data).and_then(Result::ok)
+                })
+                .collect();
+
+            batch.sort_by_key(|quote_packet| quote_packet.accept_time);
+            batch
+        })
+        .collect();
+
+    let mut heap = BinaryHeap::<BatchCursor>::new();
+
+    for (batch_idx, batch) in batches.iter().enumerate() {
+        if let Some(first) = batch.first() {
+            heap.push(BatchCursor {
+                accept_time: first.accept_time,
+                batch_idx,
+                pos: 0,
+            });
+        }
+    }
+
+    let mut window = Vec::<QuotePacket>::with_capacity(2048);
+
+    while let Some(BatchCursor { batch_idx, pos, .. }) = heap.pop() {
+        let quote_packet = batches[batch_idx][pos];
+
+        // Flush buffered quotes older than the current one, taking MAX_DELAY into account
+        for quote_packet in window.drain(
+            ..window
+                .partition_point(|probe| probe.accept_time + MAX_DELAY < quote_packet.accept_time),
+        ) {
+            emit(&quote_packet, format);
+        }
+
+        // Insert the current quote in the window at its sorted position
+        window.insert(
+            window.partition_point(|probe| probe.accept_time <= quote_packet.accept_time),
+            quote_packet,
+        );
+
+        if let Some(next) = batches[batch_idx].get(pos + 1) {
+            heap.push(BatchCursor {
+                accept_time: next.accept_time,
+                batch_idx,
+                pos: pos + 1,
+            });
+        }
+    }
+
+    // Flush the remaining quotes
+    for quote_packet in &window {
+        emit(quote_packet, format);
     }
 }
@@ -0,0 +1,108 @@
+//! A zero-copy reader for the classic pcap format, bypassing `libpcap`.
+//!
+//! pcapng is a different, block-based format and is explicitly out of scope here —
+//! `MmapCapture::open` rejects it rather than silently misreading it as classic pcap.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use memmap2::Mmap;
+use std::{fs::File, io};
+use zerocopy::{FromBytes, Immutable, KnownLayout};
+
+const MAGIC_MICROS: u32 = 0xA1B2C3D4;
+const MAGIC_MICROS_SWAPPED: u32 = 0xD4C3B2A1;
+const MAGIC_NANOS: u32 = 0xA1B23C4D;
+const MAGIC_NANOS_SWAPPED: u32 = 0x4D3CB2A1;
+
+const GLOBAL_HEADER_SIZE: usize = 24;
+const RECORD_HEADER_SIZE: usize = 16;
+
+/// The classic pcap global file header, read straight out of the mmap.
+#[derive(Copy, Clone, FromBytes, Immutable, KnownLayout)]
+#[repr(C)]
+struct GlobalHeader {
+    magic: u32,
+    _version_major: u16,
+    _version_minor: u16,
+    _this_zone: i32,
+    _sig_figs: u32,
+    _snap_len: u32,
+    _network: u32,
+}
+
+/// The per-packet record header preceding each frame, read straight out of the mmap.
+#[derive(Copy, Clone, FromBytes, Immutable, KnownLayout)]
+#[repr(C)]
+struct RecordHeader {
+    ts_sec: u32,
+    ts_frac: u32,
+    incl_len: u32,
+    _orig_len: u32,
+}
+
+/// A zero-copy reader over a memory-mapped classic-format pcap file.
+pub struct MmapCapture {
+    mmap: Mmap,
+    pos: usize,
+    big_endian: bool,
+    nanos: bool,
+}
+
+impl MmapCapture {
+    /// Memory-maps `path` and reads its global header.
+    pub fn open(path: &str) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        let header_bytes = mmap
+            .get(..GLOBAL_HEADER_SIZE)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "Truncated pcap header"))?;
+        let header =
+            GlobalHeader::read_from_bytes(header_bytes).map_err(|_| io::ErrorKind::InvalidData)?;
+
+        let (big_endian, nanos) = match header.magic {
+            MAGIC_MICROS => (false, false),
+            MAGIC_MICROS_SWAPPED => (true, false),
+            MAGIC_NANOS => (false, true),
+            MAGIC_NANOS_SWAPPED => (true, true),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Not a classic pcap file (pcapng is not supported)",
+                ))
+            }
+        };
+
+        Ok(Self {
+            mmap,
+            pos: GLOBAL_HEADER_SIZE,
+            big_endian,
+            nanos,
+        })
+    }
+
+    /// Returns the next `(timestamp, frame)` record, or `None` once exhausted or truncated.
+    pub fn next_record(&mut self) -> Option<(DateTime<Utc>, &[u8])> {
+        let header_bytes = self.mmap.get(self.pos..self.pos + RECORD_HEADER_SIZE)?;
+        let header = RecordHeader::read_from_bytes(header_bytes).ok()?;
+
+        let (ts_sec, ts_frac, incl_len) = if self.big_endian {
+            (
+                header.ts_sec.swap_bytes(),
+                header.ts_frac.swap_bytes(),
+                header.incl_len.swap_bytes(),
+            )
+        } else {
+            (header.ts_sec, header.ts_frac, header.incl_len)
+        };
+
+        let data_start = self.pos + RECORD_HEADER_SIZE;
+        let data = self.mmap.get(data_start..data_start + incl_len as usize)?;
+        self.pos = data_start + incl_len as usize;
+
+        let micros = if self.nanos { ts_frac / 1_000 } else { ts_frac };
+        let timestamp =
+            DateTime::from_timestamp(ts_sec as i64, 0)? + TimeDelta::microseconds(micros as i64);
+
+        Some((timestamp, data))
+    }
+}